@@ -0,0 +1,50 @@
+use std::fmt;
+
+/// An error encountered while scanning source code into tokens.
+///
+/// Unlike a panic, a `LexError` carries enough information (at minimum the
+/// line it occurred on) for the caller to keep going and report every
+/// problem in a source file, not just the first one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    UnterminatedString { line: u32, column: u32 },
+    MalformedNumber { line: u32, column: u32, lexeme: String },
+    UnexpectedChar { line: u32, column: u32, character: char },
+    MalformedEscapeSequence { line: u32, column: u32, character: char },
+}
+
+impl LexError {
+    pub fn line(&self) -> u32 {
+        match self {
+            LexError::UnterminatedString { line, .. } => *line,
+            LexError::MalformedNumber { line, .. } => *line,
+            LexError::UnexpectedChar { line, .. } => *line,
+            LexError::MalformedEscapeSequence { line, .. } => *line,
+        }
+    }
+
+    pub fn column(&self) -> u32 {
+        match self {
+            LexError::UnterminatedString { column, .. } => *column,
+            LexError::MalformedNumber { column, .. } => *column,
+            LexError::UnexpectedChar { column, .. } => *column,
+            LexError::MalformedEscapeSequence { column, .. } => *column,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            LexError::UnterminatedString { .. } => "unterminated string".to_string(),
+            LexError::MalformedNumber { lexeme, .. } => format!("malformed number '{}'", lexeme),
+            LexError::UnexpectedChar { character, .. } => {
+                format!("unexpected character '{}'", character)
+            }
+            LexError::MalformedEscapeSequence { character, .. } => {
+                format!("malformed escape sequence '\\{}'", character)
+            }
+        };
+        write!(f, "[line {}] Error: {}", self.line(), message)
+    }
+}
@@ -3,4 +3,6 @@ pub enum Literal {
     LoxNumber(f64),
     LoxString(String),
     LoxIdentifier(String),
+    LoxBool(bool),
+    LoxNil,
 }
@@ -0,0 +1,7 @@
+mod error;
+pub mod literal;
+mod scanner;
+mod token;
+
+pub use scanner::Scanner;
+pub use token::{Token, TokenType};
@@ -1,6 +1,6 @@
+use crate::lexer::error::LexError;
 use crate::lexer::literal::Literal;
 use crate::lexer::token::{Token, TokenType};
-use std::iter::FromIterator;
 use std::iter::Peekable;
 use std::str::Chars;
 
@@ -18,6 +18,7 @@ pub struct Scanner<'a> {
     // we will always increment them together.
     stream: Peekable<Chars<'a>>,
     line: u32,
+    column: u32,
 }
 
 impl Scanner<'_> {
@@ -27,118 +28,351 @@ impl Scanner<'_> {
         Scanner {
             stream: source.chars().peekable(),
             line: 1,
+            column: 1,
         }
     }
 
-    fn tokenize_number(&mut self, ch: char) -> Option<Token> {
+    /// Pulls the next character off the stream, keeping `line` and `column`
+    /// in sync with how much of the source has been consumed.
+    fn advance(&mut self) -> Option<char> {
+        let ch = self.stream.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    /// Discards characters up to and including the closing `"` (or to EOF,
+    /// if there isn't one), so a lexer error raised partway through a
+    /// string literal doesn't leave the stream positioned mid-string for
+    /// the next call to `next()`. Mirrors the main string loop's escape
+    /// handling: a `\"` encountered while skipping is an escaped quote,
+    /// not the end of the literal, so the backslash and the character
+    /// after it are consumed together.
+    fn skip_rest_of_string(&mut self) {
+        while let Some(&c) = self.stream.peek() {
+            self.advance();
+            match c {
+                '"' => break,
+                '\\' => {
+                    self.advance();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Scans the whole source, collecting every token on success or every
+    /// `LexError` encountered instead of stopping at the first one.
+    pub fn scan_tokens(&mut self) -> Result<Vec<Token>, Vec<LexError>> {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        for result in &mut *self {
+            match result {
+                Ok(token) => tokens.push(token),
+                Err(err) => errors.push(err),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        tokens.push(Token::new(TokenType::EOF, "", self.line, self.column));
+        Ok(tokens)
+    }
+
+    fn tokenize_number(&mut self, ch: char, column: u32) -> Result<Token, LexError> {
         let mut ident = String::from(ch);
         while let Some(&x) = self.stream.peek() {
             match x {
                 '0'..='9' | '.' => {
                     ident.push(x);
-                    self.stream.next();
+                    self.advance();
                 }
                 _ => {
                     break;
                 }
             }
         }
-        let value = ident.parse::<f64>().expect("Could not parse into float");
-        Some(Token::new(
-            TokenType::Number(Literal::LoxNumber(value)),
-            &ident,
-            self.line,
-        ))
+        match ident.parse::<f64>() {
+            Ok(value) => Ok(Token::new(
+                TokenType::Number(Literal::LoxNumber(value)),
+                &ident,
+                self.line,
+                column,
+            )),
+            Err(_) => Err(LexError::MalformedNumber {
+                line: self.line,
+                column,
+                lexeme: ident,
+            }),
+        }
     }
 }
 
-/// This method allows the Scanner to iterate over Tokens
+/// This method allows the Scanner to iterate over Tokens, yielding a `LexError`
+/// instead of panicking when the source can't be tokenized.
 impl Iterator for Scanner<'_> {
-    type Item = Token;
+    type Item = Result<Token, LexError>;
 
     /// Moves forward through the stream of characters, constructing
     /// a token.
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(ch) = self.stream.next() {
+        loop {
+            let start_column = self.column;
+            let ch = self.advance()?;
             match ch {
-                '(' => return Some(Token::new(TokenType::LeftParen, "(", self.line)),
-                ')' => return Some(Token::new(TokenType::RightParen, ")", self.line)),
-                '{' => return Some(Token::new(TokenType::LeftBrace, "{", self.line)),
-                '}' => return Some(Token::new(TokenType::RightBrace, "}", self.line)),
-                ',' => return Some(Token::new(TokenType::Comma, ",", self.line)),
-                '.' => return Some(Token::new(TokenType::Dot, ".", self.line)),
-                '-' => return Some(Token::new(TokenType::Minus, "-", self.line)),
-                '+' => return Some(Token::new(TokenType::Plus, "+", self.line)),
-                ';' => return Some(Token::new(TokenType::Semicolon, ";", self.line)),
-                '*' => return Some(Token::new(TokenType::Star, "*", self.line)),
+                '(' => {
+                    return Some(Ok(Token::new(
+                        TokenType::LeftParen,
+                        "(",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                ')' => {
+                    return Some(Ok(Token::new(
+                        TokenType::RightParen,
+                        ")",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '{' => {
+                    return Some(Ok(Token::new(
+                        TokenType::LeftBrace,
+                        "{",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '}' => {
+                    return Some(Ok(Token::new(
+                        TokenType::RightBrace,
+                        "}",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                ',' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Comma,
+                        ",",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '.' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Dot,
+                        ".",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '-' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Minus,
+                        "-",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '+' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Plus,
+                        "+",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                ';' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Semicolon,
+                        ";",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '*' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Star,
+                        "*",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                ':' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Colon,
+                        ":",
+                        self.line,
+                        start_column,
+                    )))
+                }
+                '?' => {
+                    return Some(Ok(Token::new(
+                        TokenType::Question,
+                        "?",
+                        self.line,
+                        start_column,
+                    )))
+                }
                 '!' => {
                     if self.stream.peek() == Some(&'=') {
-                        self.stream.next();
-                        return Some(Token::new(TokenType::BangEqual, "!=", self.line));
+                        self.advance();
+                        return Some(Ok(Token::new(
+                            TokenType::BangEqual,
+                            "!=",
+                            self.line,
+                            start_column,
+                        )));
                     } else {
-                        return Some(Token::new(TokenType::Bang, "=", self.line));
+                        return Some(Ok(Token::new(
+                            TokenType::Bang,
+                            "=",
+                            self.line,
+                            start_column,
+                        )));
                     }
                 }
                 '=' => {
                     if self.stream.peek() == Some(&'=') {
-                        self.stream.next();
-                        return Some(Token::new(TokenType::EqualEqual, "==", self.line));
+                        self.advance();
+                        return Some(Ok(Token::new(
+                            TokenType::EqualEqual,
+                            "==",
+                            self.line,
+                            start_column,
+                        )));
                     } else {
-                        return Some(Token::new(TokenType::Equal, "=", self.line));
+                        return Some(Ok(Token::new(
+                            TokenType::Equal,
+                            "=",
+                            self.line,
+                            start_column,
+                        )));
                     }
                 }
                 '>' => {
                     if self.stream.peek() == Some(&'=') {
-                        self.stream.next();
-                        return Some(Token::new(TokenType::GreaterEqual, ">=", self.line));
+                        self.advance();
+                        return Some(Ok(Token::new(
+                            TokenType::GreaterEqual,
+                            ">=",
+                            self.line,
+                            start_column,
+                        )));
                     } else {
-                        return Some(Token::new(TokenType::Greater, ">", self.line));
+                        return Some(Ok(Token::new(
+                            TokenType::Greater,
+                            ">",
+                            self.line,
+                            start_column,
+                        )));
                     }
                 }
                 '<' => {
                     if self.stream.peek() == Some(&'=') {
-                        self.stream.next();
-                        return Some(Token::new(TokenType::LessEqual, "<=", self.line));
+                        self.advance();
+                        return Some(Ok(Token::new(
+                            TokenType::LessEqual,
+                            "<=",
+                            self.line,
+                            start_column,
+                        )));
                     } else {
-                        return Some(Token::new(TokenType::Less, "<", self.line));
+                        return Some(Ok(Token::new(
+                            TokenType::Less,
+                            "<",
+                            self.line,
+                            start_column,
+                        )));
                     }
                 }
                 '/' => {
                     if self.stream.peek() == Some(&'/') {
-                        while self.stream.peek() != Some(&'\n') && self.stream.next().is_some() {
-                            self.stream.next();
+                        while self.stream.peek() != Some(&'\n') && self.advance().is_some() {
+                            self.advance();
                         }
                     } else if self.stream.peek() == Some(&'*') {
                         let mut just_consumed = '\0';
                         while !(just_consumed == '*' && self.stream.peek() == Some(&'/'))
                             && self.stream.peek().is_some()
                         {
-                            just_consumed = self.stream.next().unwrap();
-                            if just_consumed == '\n' {
-                                self.line += 1;
-                            }
+                            just_consumed = self.advance().unwrap();
                         }
                         // Consume the closing /*  */ characters.
-                        self.stream.next();
+                        self.advance();
                     } else {
-                        return Some(Token::new(TokenType::Slash, "/", self.line));
+                        return Some(Ok(Token::new(
+                            TokenType::Slash,
+                            "/",
+                            self.line,
+                            start_column,
+                        )));
                     }
                 }
                 '"' => {
-                    let mut lexeme = vec![];
-                    while self.stream.peek() != Some(&'"') {
-                        let next_ch = self.stream.next().expect("No closing quote found");
-                        println!("{}", next_ch);
-                        lexeme.push(next_ch);
+                    let start_line = self.line;
+                    let mut value = String::new();
+                    loop {
+                        match self.stream.peek() {
+                            Some('"') => break,
+                            Some('\\') => {
+                                let backslash_line = self.line;
+                                let backslash_column = self.column;
+                                self.advance();
+                                match self.advance() {
+                                    Some('n') => value.push('\n'),
+                                    Some('t') => value.push('\t'),
+                                    Some('r') => value.push('\r'),
+                                    Some('\\') => value.push('\\'),
+                                    Some('"') => value.push('"'),
+                                    Some(other) => {
+                                        let error = LexError::MalformedEscapeSequence {
+                                            line: backslash_line,
+                                            column: backslash_column,
+                                            character: other,
+                                        };
+                                        // Consume the rest of this string literal so the
+                                        // next call to `next()` doesn't mistake its
+                                        // still-unconsumed closing quote for the start of
+                                        // a new string.
+                                        self.skip_rest_of_string();
+                                        return Some(Err(error));
+                                    }
+                                    None => {
+                                        return Some(Err(LexError::UnterminatedString {
+                                            line: start_line,
+                                            column: start_column,
+                                        }));
+                                    }
+                                }
+                            }
+                            Some(_) => {
+                                // A literal newline inside the string is kept verbatim;
+                                // `advance` already bumps `self.line` for us.
+                                value.push(self.advance().unwrap());
+                            }
+                            None => {
+                                return Some(Err(LexError::UnterminatedString {
+                                    line: start_line,
+                                    column: start_column,
+                                }));
+                            }
+                        }
                     }
                     // Consume closing "
-                    self.stream.next();
-                    let s = String::from_iter(lexeme);
-                    return Some(Token::new(
-                        TokenType::String(Literal::LoxIdentifier(s.clone())),
-                        s.clone().as_str(),
-                        self.line,
-                    ));
+                    self.advance();
+                    return Some(Ok(Token::new(
+                        TokenType::String(Literal::LoxString(value.clone())),
+                        value.as_str(),
+                        start_line,
+                        start_column,
+                    )));
                 }
                 'a'..='z' | 'A'..='Z' => {
                     let mut ident = String::from(ch);
@@ -146,7 +380,7 @@ impl Iterator for Scanner<'_> {
                         match x {
                             'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => {
                                 ident.push(x);
-                                self.stream.next();
+                                self.advance();
                             }
                             _ => {
                                 break;
@@ -154,40 +388,44 @@ impl Iterator for Scanner<'_> {
                         }
                     }
                     let token = match ident.as_str() {
-                        "and" => Token::new(TokenType::And, &ident, self.line),
-                        "class" => Token::new(TokenType::Class, &ident, self.line),
-                        "else" => Token::new(TokenType::Else, &ident, self.line),
-                        "false" => Token::new(TokenType::False, &ident, self.line),
-                        "for" => Token::new(TokenType::For, &ident, self.line),
-                        "fun" => Token::new(TokenType::Fun, &ident, self.line),
-                        "if" => Token::new(TokenType::If, &ident, self.line),
-                        "nil" => Token::new(TokenType::Nil, &ident, self.line),
-                        "or" => Token::new(TokenType::Or, &ident, self.line),
-                        "print" => Token::new(TokenType::Print, &ident, self.line),
-                        "return" => Token::new(TokenType::Return, &ident, self.line),
-                        "super" => Token::new(TokenType::Super, &ident, self.line),
-                        "this" => Token::new(TokenType::This, &ident, self.line),
-                        "true" => Token::new(TokenType::True, &ident, self.line),
-                        "var" => Token::new(TokenType::Var, &ident, self.line),
-                        "while" => Token::new(TokenType::While, &ident, self.line),
+                        "and" => Token::new(TokenType::And, &ident, self.line, start_column),
+                        "class" => Token::new(TokenType::Class, &ident, self.line, start_column),
+                        "else" => Token::new(TokenType::Else, &ident, self.line, start_column),
+                        "false" => Token::new(TokenType::False, &ident, self.line, start_column),
+                        "for" => Token::new(TokenType::For, &ident, self.line, start_column),
+                        "fun" => Token::new(TokenType::Fun, &ident, self.line, start_column),
+                        "if" => Token::new(TokenType::If, &ident, self.line, start_column),
+                        "nil" => Token::new(TokenType::Nil, &ident, self.line, start_column),
+                        "or" => Token::new(TokenType::Or, &ident, self.line, start_column),
+                        "print" => Token::new(TokenType::Print, &ident, self.line, start_column),
+                        "return" => Token::new(TokenType::Return, &ident, self.line, start_column),
+                        "super" => Token::new(TokenType::Super, &ident, self.line, start_column),
+                        "this" => Token::new(TokenType::This, &ident, self.line, start_column),
+                        "true" => Token::new(TokenType::True, &ident, self.line, start_column),
+                        "var" => Token::new(TokenType::Var, &ident, self.line, start_column),
+                        "while" => Token::new(TokenType::While, &ident, self.line, start_column),
                         _ => Token::new(
                             TokenType::Identifier(Literal::LoxIdentifier(ident.clone())),
                             &ident,
                             self.line,
+                            start_column,
                         ),
                     };
-                    return Some(token);
+                    return Some(Ok(token));
                 }
                 '0'..='9' => {
-                    return self.tokenize_number(ch);
+                    return Some(self.tokenize_number(ch, start_column));
                 }
-                '\n' => {
-                    self.line += 1;
+                ' ' | '\t' | '\r' | '\n' => {}
+                _ => {
+                    return Some(Err(LexError::UnexpectedChar {
+                        line: self.line,
+                        column: start_column,
+                        character: ch,
+                    }));
                 }
-                _ => {}
             }
         }
-        None
     }
 }
 
@@ -199,15 +437,98 @@ mod tests {
     fn peek_shows_current_element() {
         let source = "1 + 2 + 3";
         let mut scanner = Scanner::from_source(source).peekable();
-        if let Some(Token { token_type: t, .. }) = scanner.next() {
-            assert_eq!(t, TokenType::Number);
-        } else {
-            unreachable!("This should fail");
+        match scanner.next() {
+            Some(Ok(Token {
+                token_type: TokenType::Number(_),
+                ..
+            })) => {}
+            other => unreachable!("expected a number token, got {:?}", other),
         }
-        if let Some(Token { token_type: t, .. }) = scanner.next() {
-            assert_eq!(t, TokenType::Plus);
-        } else {
-            unreachable!("The 2nd element was not Plus")
+        match scanner.next() {
+            Some(Ok(Token {
+                token_type: TokenType::Plus,
+                ..
+            })) => {}
+            other => unreachable!("expected a plus token, got {:?}", other),
         }
     }
+
+    #[test]
+    fn colon_and_question_are_recognized() {
+        let mut scanner = Scanner::from_source("a ? b : c");
+        let token_types: Vec<TokenType> = scanner
+            .scan_tokens()
+            .expect("source is valid")
+            .into_iter()
+            .map(|t| t.token_type)
+            .collect();
+        assert!(token_types.contains(&TokenType::Question));
+        assert!(token_types.contains(&TokenType::Colon));
+    }
+
+    #[test]
+    fn scan_tokens_appends_an_eof_token() {
+        let mut scanner = Scanner::from_source("1");
+        let tokens = scanner.scan_tokens().expect("1 is valid source");
+        assert_eq!(tokens.last().unwrap().token_type, TokenType::EOF);
+    }
+
+    #[test]
+    fn tokens_record_the_column_where_the_lexeme_started() {
+        let mut scanner = Scanner::from_source("  foo");
+        match scanner.next() {
+            Some(Ok(token)) => assert_eq!(token.column(), 3),
+            other => unreachable!("expected an identifier token, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn string_escape_sequences_are_translated() {
+        let mut scanner = Scanner::from_source("\"a\\tb\\n\\\"c\\\"\"");
+        match scanner.next() {
+            Some(Ok(Token {
+                token_type: TokenType::String(Literal::LoxString(s)),
+                ..
+            })) => assert_eq!(s, "a\tb\n\"c\""),
+            other => unreachable!("expected a string token, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unrecognized_escape_sequence_is_an_error() {
+        let mut scanner = Scanner::from_source("\"\\q\"");
+        let errors = scanner.scan_tokens().expect_err("expected a lex error");
+        assert_eq!(
+            errors,
+            vec![LexError::MalformedEscapeSequence {
+                line: 1,
+                column: 2,
+                character: 'q',
+            }]
+        );
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error_not_a_panic() {
+        let mut scanner = Scanner::from_source("\"unterminated");
+        let errors = scanner.scan_tokens().expect_err("expected a lex error");
+        assert_eq!(
+            errors,
+            vec![LexError::UnterminatedString { line: 1, column: 1 }]
+        );
+    }
+
+    #[test]
+    fn malformed_number_is_an_error_not_a_panic() {
+        let mut scanner = Scanner::from_source("1.2.3");
+        let errors = scanner.scan_tokens().expect_err("expected a lex error");
+        assert_eq!(
+            errors,
+            vec![LexError::MalformedNumber {
+                line: 1,
+                column: 1,
+                lexeme: "1.2.3".to_string(),
+            }]
+        );
+    }
 }
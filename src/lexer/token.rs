@@ -14,6 +14,8 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Colon,
+    Question,
 
     // One or two character tokens.
     Bang,
@@ -51,19 +53,29 @@ pub enum TokenType {
     EOF,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     lexeme: String,
     line: u32,
+    column: u32,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, lexeme: &str, line: u32) -> Token {
+    pub fn new(token_type: TokenType, lexeme: &str, line: u32, column: u32) -> Token {
         Token {
             token_type: token_type,
             lexeme: String::from(lexeme),
             line,
+            column,
         }
     }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn column(&self) -> u32 {
+        self.column
+    }
 }
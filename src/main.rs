@@ -4,9 +4,11 @@ extern crate lazy_static;
 
 
 mod lexer;
+mod parser;
 
 use clap::{App, Arg};
 use lexer::Scanner;
+use parser::Parser;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::{self, Read};
@@ -64,14 +66,29 @@ fn run_prompt() -> std::io::Result<()> {
 }
 
 fn run(program: &str) -> std::io::Result<()> {
-    println!("-- {}", program);
-
-    // Scanner scans program into tokens
     let mut scanner = Scanner::from_source(program);
-    let tokens = scanner.scan_tokens();
-    // For now, print the tokens
-    for token in tokens.iter() {
-        println!("{:?}", token);
+    match scanner.scan_tokens() {
+        Ok(tokens) => {
+            let mut parser = Parser::new(tokens);
+            // For now, print the parsed expression tree
+            match parser.parse() {
+                Ok(exprs) => {
+                    for expr in exprs {
+                        println!("{:?}", expr);
+                    }
+                }
+                Err(errors) => {
+                    for error in errors {
+                        println!("{}", error);
+                    }
+                }
+            }
+        }
+        Err(errors) => {
+            for error in errors {
+                println!("{}", error);
+            }
+        }
     }
     Ok(())
 }
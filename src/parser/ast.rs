@@ -1,3 +1,4 @@
+use crate::lexer::literal::Literal;
 use crate::lexer::Token;
 /*
 EXPR:
@@ -10,62 +11,65 @@ EXPR:
     "Logical  : Expr left, Token operator, Expr right",
     "Set      : Expr object, Token name, Expr value",
     "Super    : Token keyword, Token method",
+    "Ternary  : Expr condition, Expr then_branch, Expr else_branch",
     "This     : Token keyword",
     "Unary    : Token operator, Expr right",
     "Variable : Token name"
 */
 
+/// The recursive variants box their children: `Expr` itself has no fixed
+/// size, so a self-referential variant has to live behind a pointer.
+#[derive(Debug, Clone)]
 pub enum Expr {
     Assign {
         name: Token,
-        value: Expr,
+        value: Box<Expr>,
     },
     Binary {
-        left: Expr,
+        left: Box<Expr>,
         operator: Token,
-        right: Expr,
+        right: Box<Expr>,
     },
     Call {
-        callee: Expr,
+        callee: Box<Expr>,
         paren: Token,
         arguments: Vec<Expr>,
     },
     Get {
-        object: Expr,
+        object: Box<Expr>,
         name: Token,
     },
     Grouping {
-        expression: Expr,
+        expression: Box<Expr>,
     },
-    NumberLiteral {
-        value: LoxNumber,
-    },
-    IdentifierLiteral {
-        value: LoxIdentifier,
-    },
-    StringLiteral {
-        value: LoxString,
+    Literal {
+        value: Literal,
     },
     Logical {
-        left: Expr,
+        left: Box<Expr>,
         operator: Token,
-        right: Expr,
+        right: Box<Expr>,
     },
     Set {
-        object: Expr,
+        object: Box<Expr>,
         name: Token,
-        value: Expr,
+        value: Box<Expr>,
     },
     Super {
         keyword: Token,
         method: Token,
     },
+    Ternary {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
     This {
         keyword: Token,
     },
     Unary {
         operator: Token,
-        right: Expr,
+        right: Box<Expr>,
     },
     Variable {
         name: Token,
@@ -0,0 +1,21 @@
+use crate::lexer::Token;
+use std::fmt;
+
+/// An error encountered while parsing tokens into an `Expr`, carrying the
+/// offending token so callers can report where in the source it happened.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub token: Token,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[line {}] Error: {}",
+            self.token.line(),
+            self.message
+        )
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ast;
+mod error;
+mod parser;
+
+pub use parser::Parser;
@@ -1,27 +1,337 @@
-use crate::lexer::Token;
+use crate::lexer::literal::Literal;
+use crate::lexer::{Token, TokenType};
+use crate::parser::ast::Expr;
+use crate::parser::error::ParseError;
 
-struct Parser {
+/// Recursive-descent parser over the token stream produced by the `Scanner`.
+///
+/// Each grammar rule below (expression, equality, comparison, ...) is one
+/// precedence level, from loosest to tightest binding, following the
+/// standard Lox grammar:
+///
+/// ```text
+/// expression -> equality
+/// equality   -> comparison ( ( "!=" | "==" ) comparison )*
+/// comparison -> term ( ( ">" | ">=" | "<" | "<=" ) term )*
+/// term       -> factor ( ( "-" | "+" ) factor )*
+/// factor     -> unary ( ( "/" | "*" ) unary )*
+/// unary      -> ( "!" | "-" ) unary | primary
+/// primary    -> NUMBER | STRING | "true" | "false" | "nil" | "(" expression ")"
+/// ```
+pub struct Parser {
     tokens: Vec<Token>,
-    current: i64,
+    current: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        self {
-            tokens,
-            current: 0,
+        Parser { tokens, current: 0 }
+    }
+
+    /// Parses the token stream as a sequence of top-level expressions
+    /// (optionally `;`-separated), stopping at EOF. A parse error doesn't
+    /// abort the whole parse: `synchronize()` skips to the next likely
+    /// expression boundary and parsing resumes from there, so the caller
+    /// gets every independent error in the source, not just the first.
+    ///
+    /// This returns `Vec<Expr>` rather than a single `Expr`: once parsing
+    /// can resume after an error, a single top-level expression can no
+    /// longer represent everything that got parsed, so the collection has
+    /// to grow to match.
+    pub fn parse(&mut self) -> Result<Vec<Expr>, Vec<ParseError>> {
+        let mut exprs = Vec::new();
+        let mut errors = Vec::new();
+        while !self.is_at_end() {
+            match self.expression() {
+                Ok(expr) => {
+                    self.match_any(&[TokenType::Semicolon]);
+                    exprs.push(expr);
+                }
+                Err(err) => {
+                    errors.push(err);
+                    self.synchronize();
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(exprs)
+        } else {
+            Err(errors)
         }
     }
-}
 
-fn expression() -> Expr {
-    equality()
-}
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        self.equality()
+    }
+
+    fn equality(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.comparison()?;
+        while self.match_any(&[TokenType::BangEqual, TokenType::EqualEqual]) {
+            let operator = self.previous();
+            let right = self.comparison()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+        while self.match_any(&[
+            TokenType::Greater,
+            TokenType::GreaterEqual,
+            TokenType::Less,
+            TokenType::LessEqual,
+        ]) {
+            let operator = self.previous();
+            let right = self.term()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
+        while self.match_any(&[TokenType::Minus, TokenType::Plus]) {
+            let operator = self.previous();
+            let right = self.factor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        while self.match_any(&[TokenType::Slash, TokenType::Star]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
 
-fn equality() -> Expr {
-    let mut expr: Expr = comparison();
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.match_any(&[TokenType::Bang, TokenType::Minus]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            return Ok(Expr::Unary {
+                operator,
+                right: Box::new(right),
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        if self.match_any(&[TokenType::False]) {
+            return Ok(Expr::Literal {
+                value: Literal::LoxBool(false),
+            });
+        }
+        if self.match_any(&[TokenType::True]) {
+            return Ok(Expr::Literal {
+                value: Literal::LoxBool(true),
+            });
+        }
+        if self.match_any(&[TokenType::Nil]) {
+            return Ok(Expr::Literal {
+                value: Literal::LoxNil,
+            });
+        }
+        if self.check(&TokenType::Number(Literal::LoxNumber(0.0))) {
+            if let TokenType::Number(value) = self.advance().token_type {
+                return Ok(Expr::Literal { value });
+            }
+            unreachable!("check() guarantees this token is a Number");
+        }
+        if self.check(&TokenType::String(Literal::LoxString(String::new()))) {
+            if let TokenType::String(value) = self.advance().token_type {
+                return Ok(Expr::Literal { value });
+            }
+            unreachable!("check() guarantees this token is a String");
+        }
+        if self.match_any(&[TokenType::LeftParen]) {
+            let expr = self.expression()?;
+            self.consume(TokenType::RightParen, "Expect ')' after expression.")?;
+            return Ok(Expr::Grouping {
+                expression: Box::new(expr),
+            });
+        }
+        Err(ParseError {
+            token: self.peek().clone(),
+            message: "Expect expression.".to_string(),
+        })
+    }
+
+    /// Consumes the current token if it matches `token_type`, returning it;
+    /// otherwise returns a `ParseError` carrying `message`.
+    fn consume(&mut self, token_type: TokenType, message: &str) -> Result<Token, ParseError> {
+        if self.check(&token_type) {
+            return Ok(self.advance());
+        }
+        Err(ParseError {
+            token: self.peek().clone(),
+            message: message.to_string(),
+        })
+    }
+
+    /// Advances past the current token if it is one of `types`, returning
+    /// whether a match was found.
+    fn match_any(&mut self, types: &[TokenType]) -> bool {
+        for token_type in types {
+            if self.check(token_type) {
+                self.advance();
+                return true;
+            }
+        }
+        false
+    }
 
+    /// Compares token *kinds* rather than full equality, so a variant like
+    /// `TokenType::Number` can be matched without caring what value it holds.
+    fn check(&self, token_type: &TokenType) -> bool {
+        if self.is_at_end() {
+            return false;
+        }
+        std::mem::discriminant(&self.peek().token_type) == std::mem::discriminant(token_type)
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        if !self.is_at_end() {
+            self.current += 1;
+        }
+        token
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.peek().token_type == TokenType::EOF
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current]
+    }
+
+    fn previous(&self) -> Token {
+        self.tokens[self.current - 1].clone()
+    }
+
+    /// Discards tokens until just after the next `;`, or until a token that
+    /// starts a new statement, so one syntax error doesn't swallow the rest
+    /// of the file.
+    fn synchronize(&mut self) {
+        self.advance();
+        while !self.is_at_end() {
+            if self.previous().token_type == TokenType::Semicolon {
+                return;
+            }
+            match self.peek().token_type {
+                TokenType::Class
+                | TokenType::Fun
+                | TokenType::Var
+                | TokenType::For
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Print
+                | TokenType::Return => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
 }
 
-fn comparison() -> Expr {
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens_for(source: &str) -> Vec<Token> {
+        crate::lexer::Scanner::from_source(source)
+            .scan_tokens()
+            .expect("test sources must be valid")
+    }
+
+    /// Parses `source` expecting it to hold exactly one top-level expression.
+    fn parse_one(source: &str) -> Expr {
+        let mut parser = Parser::new(tokens_for(source));
+        let mut exprs = parser.parse().expect("source must parse cleanly");
+        assert_eq!(exprs.len(), 1, "expected exactly one top-level expression");
+        exprs.remove(0)
+    }
+
+    #[test]
+    fn parses_left_associative_addition() {
+        match parse_one("1 + 2 + 3") {
+            Expr::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                assert_eq!(operator.token_type, TokenType::Plus);
+                assert!(matches!(*right, Expr::Literal { .. }));
+                assert!(matches!(*left, Expr::Binary { .. }));
+            }
+            other => unreachable!("expected a binary expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn factor_binds_tighter_than_term() {
+        match parse_one("1 + 2 * 3") {
+            Expr::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                assert_eq!(operator.token_type, TokenType::Plus);
+                assert!(matches!(*left, Expr::Literal { .. }));
+                assert!(matches!(*right, Expr::Binary { .. }));
+            }
+            other => unreachable!("expected a binary expression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_parenthesized_grouping() {
+        assert!(matches!(parse_one("(1)"), Expr::Grouping { .. }));
+    }
+
+    #[test]
+    fn parses_multiple_semicolon_separated_expressions() {
+        let mut parser = Parser::new(tokens_for("1; 2; 3"));
+        let exprs = parser.parse().expect("source must parse cleanly");
+        assert_eq!(exprs.len(), 3);
+    }
+
+    #[test]
+    fn missing_closing_paren_is_a_parse_error() {
+        let mut parser = Parser::new(tokens_for("(1"));
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn synchronize_lets_parsing_collect_more_than_one_error() {
+        // `)` isn't a valid expression start, so each one is its own error;
+        // `synchronize` should skip to just after the following `;` so the
+        // next `)` is also reported instead of the parser giving up after the
+        // first.
+        let mut parser = Parser::new(tokens_for(") 1; ) 2;"));
+        let errors = parser.parse().expect_err("malformed source must fail");
+        assert_eq!(errors.len(), 2);
+    }
+}